@@ -1,5 +1,6 @@
+use super::clock::{Clock, ClockInstant, StdClock};
 use super::{RateCounter, RateCounterImmut};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 /// A very basic non-rolling update counter. It counts n updates, calculates, and
 /// then resets (where n is the sample rate), which means that it takes at least
@@ -19,15 +20,19 @@ use std::time::{Duration, Instant};
 ///
 /// You can also use .update_immut() for this. Since DiscreteRateCounter is
 /// small and easily copyable, this is negligibly less efficient.
-#[derive(Clone, Copy)]
-pub struct DiscreteRateCounter {
+///
+/// `DiscreteRateCounter` is generic over its time source; `new()` uses the
+/// real system clock, and `with_clock()` allows substituting a `Clock` such
+/// as `MockClock` for testing.
+pub struct DiscreteRateCounter<C: Clock = StdClock> {
     updates_since_clear: u64,
-    time_at_last_clear: Instant,
+    time_at_last_clear: C::Instant,
     rate: f64,
     samples: u64,
+    clock: C,
 }
 
-impl DiscreteRateCounter {
+impl DiscreteRateCounter<StdClock> {
     /// Create a new DiscreteRateCounter which calculates the update rate every
     /// `samples` cycles.  Until that many cycles occur, `rate()` will
     /// return a useless value, typically 0.0.
@@ -36,11 +41,21 @@ impl DiscreteRateCounter {
     /// and keep ramping it up until it reaches your target `samples` value;
     /// however, the data near the beginning will not be useful.
     pub fn new(samples: u64) -> Self {
+        Self::with_clock(samples, StdClock)
+    }
+}
+
+impl<C: Clock> DiscreteRateCounter<C> {
+    /// Create a new DiscreteRateCounter driven by the given `Clock` instead
+    /// of the real system clock.
+    pub fn with_clock(samples: u64, clock: C) -> Self {
+        let time_at_last_clear = clock.now();
         DiscreteRateCounter {
             updates_since_clear: 0,
-            time_at_last_clear: Instant::now(),
+            time_at_last_clear,
             rate: 0.0,
-            samples: samples,
+            samples,
+            clock,
         }
     }
 
@@ -50,13 +65,13 @@ impl DiscreteRateCounter {
     }
 
     /// Return the amount of time since the rate was last recalculated. This
-    /// requires examining the system clock and is thus relatively expensive.
+    /// requires examining the clock and is thus relatively expensive.
     pub fn rate_age_duration(&self) -> Duration {
-        self.time_at_last_clear.elapsed()
+        self.clock.now().duration_since(&self.time_at_last_clear)
     }
 }
 
-impl RateCounter for DiscreteRateCounter {
+impl<C: Clock> RateCounter for DiscreteRateCounter<C> {
     fn samples(&self) -> u64 {
         self.samples
     }
@@ -69,7 +84,7 @@ impl RateCounter for DiscreteRateCounter {
         self.updates_since_clear += 1;
 
         if self.updates_since_clear >= self.samples {
-            let elapsed = self.time_at_last_clear.elapsed();
+            let elapsed = self.clock.now().duration_since(&self.time_at_last_clear);
             // Compose a f64 of the amount of time elapsed since the last
             // update; that's seconds plus nanos
             let real_time_since_clear =
@@ -79,7 +94,7 @@ impl RateCounter for DiscreteRateCounter {
             self.rate = self.updates_since_clear as f64 / real_time_since_clear;
 
             // Reset the structure
-            self.time_at_last_clear = Instant::now();
+            self.time_at_last_clear = self.clock.now();
             self.updates_since_clear = 0;
         }
     }
@@ -89,7 +104,7 @@ impl RateCounter for DiscreteRateCounter {
     }
 }
 
-impl RateCounterImmut for DiscreteRateCounter {
+impl<C: Clock> RateCounterImmut for DiscreteRateCounter<C> {
     /// Consumes the struct and returns an updated version.
     /// Call this at the beginning of each cycle of the periodic activity being
     /// measured.
@@ -112,9 +127,25 @@ impl RateCounterImmut for DiscreteRateCounter {
     }
 }
 
+impl<C: Clock + Clone> Clone for DiscreteRateCounter<C> {
+    fn clone(&self) -> Self {
+        DiscreteRateCounter {
+            updates_since_clear: self.updates_since_clear,
+            time_at_last_clear: self.time_at_last_clear,
+            rate: self.rate,
+            samples: self.samples,
+            clock: self.clock.clone(),
+        }
+    }
+}
+
+impl<C: Clock + Copy> Copy for DiscreteRateCounter<C> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
+
     #[test]
     fn test_discrete_rate_counter() {
         let mut c = DiscreteRateCounter::new(10);
@@ -181,4 +212,24 @@ mod tests {
             "Counter rate should be closer to actual rate."
         );
     }
+
+    #[test]
+    fn test_discrete_rate_counter_with_mock_clock() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = DiscreteRateCounter::with_clock(10, clock.clone());
+
+        for _ in 1..11 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+
+        // Rate should be exactly 100 Hz with 10 ms/update and a deterministic clock
+        assert!(
+            (c.rate() - 100.0).abs() < 0.001,
+            "Counter rate {} should be 100 Hz with a mock clock.",
+            c.rate()
+        );
+    }
 }
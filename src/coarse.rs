@@ -0,0 +1,171 @@
+//! A `Clock` that trades timing precision for throughput.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use super::clock::{Clock, StdClock};
+
+/// A `Clock` wrapper that reads its inner clock at most once per refresh
+/// window, returning a cached value in between.
+///
+/// Hammering the OS timer on every `.update()` in a tight loop is wasteful
+/// when the caller doesn't need sub-window precision -- `DiscreteRateCounter`'s
+/// own docs already flag reading the clock as "relatively expensive" (the
+/// dipstick metrics library calls this a "slow or imprecise clock" for the
+/// same reason). `CoarseClock` amortizes that cost: a background thread reads
+/// the inner clock once per `refresh_interval` and stores the result, while
+/// `.now()` itself just reads the cached value.
+///
+/// Because the cached value is at most `refresh_interval` old, the measured
+/// rate error introduced by a `CoarseClock` is bounded by one refresh
+/// interval per window.
+///
+/// `CoarseClock` is itself generic over its inner `Clock`, so a platform that
+/// exposes a cheaper monotonic source than the default high-precision
+/// performance counter can supply it in place of `StdClock`.
+///
+/// The background thread holds only a `Weak` reference to the clock's
+/// handle, so it notices (and exits, within one `refresh_interval`) once the
+/// last `CoarseClock`/clone referring to it has been dropped, rather than
+/// running for the life of the process.
+///
+/// # Examples
+/// ```
+/// use update_rate::{RateCounter, RollingRateCounter, CoarseClock};
+/// use std::time::Duration;
+///
+/// let clock = CoarseClock::new(Duration::from_millis(5));
+/// let mut c = RollingRateCounter::with_clock(10, clock);
+/// c.update();
+/// ```
+pub struct CoarseClock<C: Clock = StdClock> {
+    cached: Arc<Mutex<C::Instant>>,
+    // Held only by live handles to this clock; the background thread keeps a
+    // `Weak` reference to this so it can tell when it should exit.
+    handle: Arc<()>,
+}
+
+impl CoarseClock<StdClock> {
+    /// Create a new `CoarseClock` backed by the real system clock, refreshing
+    /// its cached timestamp at most once every `refresh_interval`.
+    pub fn new(refresh_interval: Duration) -> Self {
+        Self::with_clock(refresh_interval, StdClock)
+    }
+}
+
+impl<C> CoarseClock<C>
+where
+    C: Clock + Clone + Send + 'static,
+    C::Instant: Send,
+{
+    /// Create a new `CoarseClock` backed by the given inner `Clock`,
+    /// refreshing its cached timestamp at most once every `refresh_interval`.
+    pub fn with_clock(refresh_interval: Duration, inner: C) -> Self {
+        let cached = Arc::new(Mutex::new(inner.now()));
+        let handle = Arc::new(());
+
+        let worker_cached = Arc::clone(&cached);
+        let weak_handle = Arc::downgrade(&handle);
+        thread::spawn(move || {
+            // Exit as soon as every `CoarseClock` handle sharing `weak_handle`
+            // has been dropped, instead of sleeping forever.
+            while weak_handle.upgrade().is_some() {
+                thread::sleep(refresh_interval);
+                if weak_handle.upgrade().is_none() {
+                    break;
+                }
+                let now = inner.now();
+                *worker_cached.lock().unwrap() = now;
+            }
+        });
+
+        CoarseClock { cached, handle }
+    }
+}
+
+impl<C: Clock> Clock for CoarseClock<C> {
+    type Instant = C::Instant;
+
+    fn now(&self) -> C::Instant {
+        *self.cached.lock().unwrap()
+    }
+}
+
+impl<C: Clock> Clone for CoarseClock<C> {
+    fn clone(&self) -> Self {
+        CoarseClock {
+            cached: Arc::clone(&self.cached),
+            handle: Arc::clone(&self.handle),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::ClockInstant;
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn test_coarse_clock_caches_within_a_refresh_window() {
+        let clock = CoarseClock::new(Duration::from_millis(50));
+        let first = clock.now();
+        sleep(Duration::from_millis(5));
+        let second = clock.now();
+
+        assert_eq!(
+            ClockInstant::duration_since(&second, &first),
+            Duration::from_secs(0),
+            "CoarseClock should return the same cached instant within a refresh window."
+        );
+    }
+
+    #[test]
+    fn test_coarse_clock_refreshes_after_the_window_elapses() {
+        let clock = CoarseClock::new(Duration::from_millis(5));
+        let first = clock.now();
+        sleep(Duration::from_millis(50));
+        let second = clock.now();
+
+        assert!(
+            ClockInstant::duration_since(&second, &first) > Duration::from_secs(0),
+            "CoarseClock should have refreshed its cached instant after the window elapsed."
+        );
+    }
+
+    #[test]
+    fn test_coarse_clock_clones_share_the_cache() {
+        let clock = CoarseClock::new(Duration::from_millis(5));
+        let clone = clock.clone();
+        sleep(Duration::from_millis(50));
+
+        assert_eq!(clock.now(), clone.now());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_coarse_clock_background_thread_exits_when_dropped() {
+        fn thread_count() -> usize {
+            std::fs::read_dir("/proc/self/task").unwrap().count()
+        }
+
+        let before = thread_count();
+        let clock = CoarseClock::new(Duration::from_millis(5));
+        // Give the background thread time to actually spawn.
+        sleep(Duration::from_millis(20));
+        assert!(
+            thread_count() > before,
+            "expected the background thread to have started"
+        );
+
+        drop(clock);
+        // Give the background thread time to notice and exit.
+        sleep(Duration::from_millis(50));
+        assert_eq!(
+            thread_count(),
+            before,
+            "background thread should have exited after its last handle was dropped"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+use super::clock::{Clock, ClockInstant, StdClock};
+use super::{RateCounter, RateCounterImmut};
+
+/// An exponentially-weighted moving-average update counter.
+///
+/// Unlike `DiscreteRateCounter` and `RollingRateCounter`, which need to
+/// accumulate a fixed number of samples before producing a meaningful rate,
+/// `EwmaRateCounter` holds only a single smoothed inter-update interval and
+/// the timestamp of the last update, giving O(1) time and constant memory
+/// regardless of the smoothing depth. It also updates continuously, which
+/// suits a live FPS readout that should track a slowdown immediately rather
+/// than waiting out a warm-up period.
+///
+/// # Usage
+///
+/// Call `.update()` every time your system starts a new update/cycle, exactly
+/// as with the other `RateCounter` implementations. You can also use
+/// `.update_immut()` for this.
+pub struct EwmaRateCounter<C: Clock = StdClock> {
+    alpha: f64,
+    smoothed: Option<f64>,
+    last: Option<C::Instant>,
+    clock: C,
+}
+
+impl EwmaRateCounter<StdClock> {
+    /// Create a new EwmaRateCounter with the given smoothing factor `alpha`.
+    ///
+    /// `alpha` must be in `(0.0, 1.0]` and controls responsiveness: values
+    /// closer to 1.0 track recent intervals more closely (snappier), while
+    /// values closer to 0.0 smooth out more noise (more stable). `rate()`
+    /// returns 0.0 until the first interval has been observed.
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not in the range `(0.0, 1.0]`.
+    pub fn new(alpha: f64) -> Self {
+        Self::with_clock(alpha, StdClock)
+    }
+}
+
+impl<C: Clock> EwmaRateCounter<C> {
+    /// Create a new EwmaRateCounter driven by the given `Clock` instead of
+    /// the real system clock.
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not in the range `(0.0, 1.0]`.
+    pub fn with_clock(alpha: f64, clock: C) -> Self {
+        assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "EwmaRateCounter alpha must be in (0.0, 1.0], got {}",
+            alpha
+        );
+        EwmaRateCounter {
+            alpha,
+            smoothed: None,
+            last: None,
+            clock,
+        }
+    }
+
+    /// Return the smoothing factor currently in use.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+}
+
+impl<C: Clock> RateCounter for EwmaRateCounter<C> {
+    /// Return the window length equivalent to the current `alpha`, via
+    /// `alpha = 2 / (samples + 1)`, so code written against `RateCounter`
+    /// can treat this like a windowed counter.
+    fn samples(&self) -> u64 {
+        ((2.0 / self.alpha) - 1.0).round() as u64
+    }
+
+    /// Set `alpha` to the value equivalent to the given window length, via
+    /// `alpha = 2 / (samples + 1)`.
+    ///
+    /// # Panics
+    /// This function will panic if given a `samples` value equal to 0.
+    fn set_samples(&mut self, samples: u64) {
+        if samples == 0 {
+            panic!("Tried to set samples of an EwmaRateCounter to 0");
+        }
+        self.alpha = 2.0 / (samples as f64 + 1.0);
+    }
+
+    fn update(&mut self) {
+        let now = self.clock.now();
+
+        if let Some(last) = self.last {
+            let delta_t = now.duration_since(&last);
+            let delta = delta_t.as_secs() as f64 + delta_t.subsec_nanos() as f64 * 1e-9;
+
+            self.smoothed = Some(match self.smoothed {
+                Some(smoothed) => self.alpha * delta + (1.0 - self.alpha) * smoothed,
+                None => delta,
+            });
+        }
+
+        self.last = Some(now);
+    }
+
+    fn rate(&self) -> f64 {
+        match self.smoothed {
+            Some(smoothed) => 1.0 / smoothed,
+            None => 0.0,
+        }
+    }
+}
+
+impl<C: Clock> RateCounterImmut for EwmaRateCounter<C> {
+    /// Consumes the struct and returns an updated version.
+    /// Call this at the beginning of each cycle of the periodic activity
+    /// being measured.
+    fn update_immut(self) -> Self {
+        let mut new = self;
+        new.update();
+        new
+    }
+}
+
+impl<C: Clock + Clone> Clone for EwmaRateCounter<C> {
+    fn clone(&self) -> Self {
+        EwmaRateCounter {
+            alpha: self.alpha,
+            smoothed: self.smoothed,
+            last: self.last,
+            clock: self.clock.clone(),
+        }
+    }
+}
+
+impl<C: Clock + Copy> Copy for EwmaRateCounter<C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_ewma_rate_counter() {
+        let mut c = EwmaRateCounter::new(0.5);
+        assert!(
+            c.rate() == 0.0,
+            "Counter should have no data before the first interval."
+        );
+
+        let sample_period = Duration::from_millis(10);
+        for _ in 1..11 {
+            // Use busy-wait because sleeping is extremely inaccurate
+            let start = Instant::now();
+            while start.elapsed() < sample_period {}
+
+            c.update();
+        }
+
+        // Rate should be 100 Hz with 10 ms/update
+        let difference = 100.0 - c.rate();
+        assert!(
+            difference < 20.0,
+            "Counter rate {} should be closer to actual rate 100.0.",
+            c.rate()
+        );
+    }
+
+    #[test]
+    fn test_ewma_rate_counter_with_mock_clock_converges() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = EwmaRateCounter::with_clock(0.5, clock.clone());
+
+        for _ in 0..20 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+
+        assert!(
+            (c.rate() - 100.0).abs() < 0.001,
+            "Counter rate {} should converge to 100 Hz on a steady cadence.",
+            c.rate()
+        );
+    }
+
+    #[test]
+    fn test_ewma_rate_counter_tracks_a_rate_change_without_a_warm_up() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        // A high alpha should react to a new rate almost immediately.
+        let mut c = EwmaRateCounter::with_clock(0.9, clock.clone());
+
+        for _ in 0..5 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+        assert!((c.rate() - 100.0).abs() < 1.0);
+
+        // Slow down to 50 Hz; a single update should already move the rate
+        // a long way towards it.
+        clock.advance(Duration::from_millis(20));
+        c.update();
+        assert!(
+            c.rate() < 80.0,
+            "Counter rate {} should have moved sharply towards the new 50 Hz cadence.",
+            c.rate()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ewma_rate_counter_rejects_zero_alpha() {
+        EwmaRateCounter::new(0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ewma_rate_counter_rejects_alpha_above_one() {
+        EwmaRateCounter::new(1.1);
+    }
+
+    #[test]
+    fn test_ewma_rate_counter_samples_round_trip() {
+        let mut c = EwmaRateCounter::new(0.5);
+        c.set_samples(9);
+        // alpha = 2 / (9 + 1) = 0.2, which maps back to 9 samples.
+        assert_eq!(c.samples(), 9);
+    }
+}
@@ -34,6 +34,20 @@ pub use base::DiscreteRateCounter;
 mod rolling;
 pub use rolling::RollingRateCounter;
 
+mod ewma;
+pub use ewma::EwmaRateCounter;
+
+mod clock;
+pub use clock::{Clock, ClockInstant, MockClock, StdClock};
+
+mod coarse;
+pub use coarse::CoarseClock;
+
+mod stats;
+pub use stats::RateStats;
+
+mod format;
+
 pub trait RateCounter {
     /// Return the current number of samples the UpdateRateCounter is measuring.
     fn samples(&self) -> u64;
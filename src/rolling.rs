@@ -1,13 +1,15 @@
-use std::time::Instant;
-use std::collections::VecDeque;
+use super::clock::{Clock, ClockInstant, StdClock};
+use super::stats::RateStats;
 use super::RateCounter;
+use std::collections::VecDeque;
 
 /// A rolling update counter. It records as many updates as the given sample rate
 /// and re-calculates the average update time on each call to update.
 ///
-/// Generally, this is to be preferred over the discrete version. However, for very
-/// high values of `sample`, this can be quite inefficient, especially if the rate
-/// value isn't needed during each cycle.
+/// Generally, this is to be preferred over the discrete version: pushing a new
+/// update and evicting the oldest one are both O(1), since a running sum of
+/// the window's inter-update gaps is maintained incrementally rather than
+/// recomputed from scratch on every call.
 ///
 /// # Usage
 ///
@@ -18,32 +20,96 @@ use super::RateCounter;
 /// meaningful result is produced.
 ///
 /// You can also use .update_immut() for this to avoid a mutable binding.
-#[derive(Clone)]
-pub struct RollingRateCounter {
-    updates: VecDeque<Instant>,
-    rate: f64,
+///
+/// `RollingRateCounter` is generic over its time source; `new()` uses the
+/// real system clock, and `with_clock()` allows substituting a `Clock` such
+/// as `MockClock` for testing.
+pub struct RollingRateCounter<C: Clock = StdClock> {
+    updates: VecDeque<C::Instant>,
+    /// Sum, in nanoseconds, of the gaps between consecutive entries of
+    /// `updates`. Maintained incrementally so `rate()` never has to walk
+    /// the window.
+    total_nanos: u64,
     samples: u64,
+    clock: C,
 }
 
-impl RollingRateCounter {
+impl RollingRateCounter<StdClock> {
     /// Create a new RollingRateCounter which calculates the update rate every
     /// update, averaging over a window of `update_rate` cycles.
     ///
     /// # Panics
     /// This function will panic if given a value of `samples` equal to 0.
     pub fn new(samples: u64) -> Self {
+        Self::with_clock(samples, StdClock)
+    }
+}
+
+impl<C: Clock> RollingRateCounter<C> {
+    /// Create a new RollingRateCounter driven by the given `Clock` instead of
+    /// the real system clock.
+    ///
+    /// # Panics
+    /// This function will panic if given a value of `samples` equal to 0.
+    pub fn with_clock(samples: u64, clock: C) -> Self {
         if samples == 0 {
             panic!("Tried to build a RollingRateCounter with a sample_rate of 0")
         }
         RollingRateCounter {
             updates: VecDeque::with_capacity(samples as usize),
-            rate: 0.0,
-            samples: samples,
+            total_nanos: 0,
+            samples,
+            clock,
+        }
+    }
+
+    /// Evict the oldest entry in the window, subtracting the gap it
+    /// contributed from `total_nanos`.
+    ///
+    /// # Panics
+    /// Panics if the window is empty.
+    fn evict_oldest(&mut self) {
+        let removed = self
+            .updates
+            .pop_front()
+            .expect("evict_oldest() called on an empty window");
+        if let Some(next) = self.updates.front() {
+            let gap = next.duration_since(&removed);
+            self.total_nanos -= gap.as_nanos() as u64;
         }
     }
+
+    /// Compute summary statistics (min/max/mean/percentiles/jitter) over the
+    /// inter-update intervals currently in the window.
+    ///
+    /// Unlike `rate()`, which reports a single averaged value, this surfaces
+    /// the full distribution of intervals -- a stable average can hide an
+    /// occasional stall that this will show up in `max()`/`p99()`/`jitter()`.
+    ///
+    /// # Panics
+    /// Panics if fewer than two updates have been recorded, since at least
+    /// one interval is required to compute statistics.
+    pub fn stats(&self) -> RateStats {
+        assert!(
+            self.updates.len() >= 2,
+            "RollingRateCounter::stats() requires at least two updates"
+        );
+
+        let deltas: Vec<f64> = self
+            .updates
+            .iter()
+            .zip(self.updates.iter().skip(1))
+            .map(|(earlier, later)| {
+                let delta_t = later.duration_since(earlier);
+                delta_t.as_secs() as f64 + delta_t.subsec_nanos() as f64 * 1e-9
+            })
+            .collect();
+
+        RateStats::from_deltas(deltas)
+    }
 }
 
-impl RateCounter for RollingRateCounter {
+impl<C: Clock> RateCounter for RollingRateCounter<C> {
     fn samples(&self) -> u64 {
         self.samples
     }
@@ -54,44 +120,53 @@ impl RateCounter for RollingRateCounter {
         }
         self.samples = samples;
 
-        // Remove the oldest updates until the window
-        // is the correct length
+        // Evict the oldest updates until the window is the correct length,
+        // keeping `total_nanos` in sync with what's evicted.
         while self.updates.len() > self.samples as usize {
-            self.updates.remove(0);
+            self.evict_oldest();
         }
     }
 
     fn update(&mut self) {
-        // Remove the element at the top of the queue until it's cut down to size
+        // Evict the oldest update(s) until there's room for the new one.
         while self.updates.len() >= self.samples as usize {
-            self.updates.pop_front();
+            self.evict_oldest();
         }
 
-        self.updates.push_back(Instant::now());
-
-        self.rate = 0.0;
-        for (index, _) in self.updates.iter().enumerate() {
-            if index == 0 {
-                continue;
-            }
-            // Get the time elapsed during the update interval being considered
-            let delta_t = self.updates[index].duration_since(self.updates[index - 1]);
-            let delta_t = delta_t.as_secs() as f64 + delta_t.subsec_nanos() as f64 * 1e-9;
-
-            // Average it with the rate
-            let avg_delta_t = (self.rate + delta_t) / 2.0;
-            self.rate = self.samples as f64 / avg_delta_t;
+        let now = self.clock.now();
+        if let Some(last) = self.updates.back() {
+            let gap = now.duration_since(last);
+            self.total_nanos += gap.as_nanos() as u64;
         }
+        self.updates.push_back(now);
     }
 
     fn rate(&self) -> f64 {
-        self.rate
+        // Need at least two updates to have a gap to measure.
+        if self.updates.len() < 2 {
+            0.0
+        } else {
+            (self.updates.len() - 1) as f64 / (self.total_nanos as f64 * 1e-9)
+        }
+    }
+}
+
+impl<C: Clock + Clone> Clone for RollingRateCounter<C> {
+    fn clone(&self) -> Self {
+        RollingRateCounter {
+            updates: self.updates.clone(),
+            total_nanos: self.total_nanos,
+            samples: self.samples,
+            clock: self.clock.clone(),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::{Duration, Instant};
+
     #[test]
     fn test_rolling_rate_counter() {
         let mut c = RollingRateCounter::new(10);
@@ -101,7 +176,7 @@ mod tests {
             c.rate()
         );
 
-        let sample_period = ::std::time::Duration::from_millis(10);
+        let sample_period = Duration::from_millis(10);
         for _ in 1..11 {
             // Use busy-wait because sleeping is extremely inaccurate
             let start = Instant::now();
@@ -118,4 +193,108 @@ mod tests {
             c.rate()
         );
     }
+
+    #[test]
+    fn test_rolling_rate_counter_with_mock_clock_is_deterministic() {
+        use super::super::clock::MockClock;
+
+        fn run() -> f64 {
+            let clock = MockClock::new();
+            let mut c = RollingRateCounter::with_clock(10, clock.clone());
+            for _ in 1..11 {
+                clock.advance(Duration::from_millis(10));
+                c.update();
+            }
+            c.rate()
+        }
+
+        // With a mock clock driving identical advances, two independent runs
+        // must produce exactly the same rate -- no busy-wait jitter.
+        assert_eq!(run(), run());
+    }
+
+    #[test]
+    fn test_rolling_rate_counter_with_mock_clock_is_accurate() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = RollingRateCounter::with_clock(10, clock.clone());
+        for _ in 1..11 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+
+        // With a deterministic 10ms cadence, the windowed rate should be
+        // exactly 100 Hz (previously, the order-dependent averaging formula
+        // did not converge on the true mean).
+        assert!(
+            (c.rate() - 100.0).abs() < 1e-6,
+            "Counter rate {} should be 100 Hz with a steady mock clock.",
+            c.rate()
+        );
+    }
+
+    #[test]
+    fn test_set_samples_keeps_total_nanos_consistent() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = RollingRateCounter::with_clock(10, clock.clone());
+        for _ in 1..11 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+
+        // Shrinking the window should evict the oldest entries and keep the
+        // running sum (and thus the rate) consistent with what remains.
+        c.set_samples(5);
+        assert!(
+            (c.rate() - 100.0).abs() < 1e-6,
+            "Counter rate {} should still be 100 Hz after shrinking the window.",
+            c.rate()
+        );
+    }
+
+    #[test]
+    fn test_stats_over_a_steady_window() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = RollingRateCounter::with_clock(10, clock.clone());
+
+        for _ in 0..10 {
+            clock.advance(Duration::from_millis(10));
+            c.update();
+        }
+
+        let stats = c.stats();
+        assert_eq!(stats.min(), Duration::from_millis(10));
+        assert_eq!(stats.max(), Duration::from_millis(10));
+        assert_eq!(stats.mean(), Duration::from_millis(10));
+        assert!(stats.jitter() < 1e-9, "jitter should be ~0 on a steady cadence");
+    }
+
+    #[test]
+    fn test_stats_catches_a_stall_the_mean_would_hide() {
+        use super::super::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut c = RollingRateCounter::with_clock(10, clock.clone());
+
+        for i in 0..10 {
+            // A single long stall among otherwise steady updates.
+            let gap = if i == 5 {
+                Duration::from_millis(100)
+            } else {
+                Duration::from_millis(10)
+            };
+            clock.advance(gap);
+            c.update();
+        }
+
+        let stats = c.stats();
+        assert_eq!(stats.max(), Duration::from_millis(100));
+        assert_eq!(stats.p99(), Duration::from_millis(100));
+        assert!(stats.jitter() > 0.0);
+    }
 }
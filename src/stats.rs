@@ -0,0 +1,195 @@
+//! Distribution statistics over a counter's inter-update intervals.
+
+use std::time::Duration;
+
+/// Summary statistics describing the inter-update intervals observed in a
+/// `RollingRateCounter`'s window.
+///
+/// Where `rate()` reports a single averaged value, `RateStats` exposes the
+/// full distribution of intervals between updates -- useful for catching
+/// occasional stalls that a stable average would otherwise hide.
+#[derive(Clone, Debug)]
+pub struct RateStats {
+    min: Duration,
+    max: Duration,
+    mean: Duration,
+    jitter: f64,
+    sorted_deltas: Vec<f64>,
+}
+
+impl RateStats {
+    /// Build a `RateStats` from the window's consecutive inter-update
+    /// intervals, given in seconds.
+    ///
+    /// # Panics
+    /// Panics if `deltas` is empty, since at least one interval is required.
+    pub(crate) fn from_deltas(deltas: Vec<f64>) -> Self {
+        assert!(
+            !deltas.is_empty(),
+            "RateStats requires at least one interval"
+        );
+
+        // Single pass for min/max/mean/variance.
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for &d in &deltas {
+            sum += d;
+            sum_sq += d * d;
+            min = min.min(d);
+            max = max.max(d);
+        }
+        let n = deltas.len() as f64;
+        let mean = sum / n;
+        // Guard against negative variance from floating point error when
+        // all deltas are (nearly) identical.
+        let variance = (sum_sq / n - mean * mean).max(0.0);
+        let jitter = variance.sqrt();
+
+        let mut sorted_deltas = deltas;
+        sorted_deltas.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        RateStats {
+            min: Duration::from_secs_f64(min),
+            max: Duration::from_secs_f64(max),
+            mean: Duration::from_secs_f64(mean),
+            jitter,
+            sorted_deltas,
+        }
+    }
+
+    /// Return the shortest inter-update interval in the window.
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// Return the longest inter-update interval in the window.
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// Return the mean inter-update interval in the window.
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+
+    /// Return the standard deviation of inter-update intervals, in seconds.
+    ///
+    /// This is the jitter of the measured rate: a steady cadence has jitter
+    /// near zero, while occasional stalls push it up even when the mean
+    /// looks fine.
+    pub fn jitter(&self) -> f64 {
+        self.jitter
+    }
+
+    /// Return the `p`th percentile (in `(0, 100]`) of inter-update
+    /// intervals, using the nearest-rank method.
+    ///
+    /// # Panics
+    /// Panics if `p` is not in the range `(0, 100]`.
+    pub fn percentile(&self, p: f64) -> Duration {
+        assert!(
+            p > 0.0 && p <= 100.0,
+            "percentile must be in (0, 100], got {}",
+            p
+        );
+        let n = self.sorted_deltas.len();
+        let rank = (p / 100.0 * n as f64).ceil() as usize;
+        let index = rank.saturating_sub(1).min(n - 1);
+        Duration::from_secs_f64(self.sorted_deltas[index])
+    }
+
+    /// The median (p50) inter-update interval.
+    pub fn p50(&self) -> Duration {
+        self.percentile(50.0)
+    }
+
+    /// The p90 inter-update interval.
+    pub fn p90(&self) -> Duration {
+        self.percentile(90.0)
+    }
+
+    /// The p99 inter-update interval.
+    pub fn p99(&self) -> Duration {
+        self.percentile(99.0)
+    }
+
+    /// Return `duration` expressed as an instantaneous rate in Hertz
+    /// (`1.0 / duration`).
+    pub fn hz(duration: Duration) -> f64 {
+        1.0 / duration.as_secs_f64()
+    }
+
+    /// `min()`, expressed as an instantaneous rate in Hertz.
+    pub fn min_hz(&self) -> f64 {
+        Self::hz(self.min)
+    }
+
+    /// `max()`, expressed as an instantaneous rate in Hertz.
+    pub fn max_hz(&self) -> f64 {
+        Self::hz(self.max)
+    }
+
+    /// `mean()`, expressed as an instantaneous rate in Hertz.
+    pub fn mean_hz(&self) -> f64 {
+        Self::hz(self.mean)
+    }
+
+    /// `p50()`, expressed as an instantaneous rate in Hertz.
+    pub fn p50_hz(&self) -> f64 {
+        Self::hz(self.p50())
+    }
+
+    /// `p90()`, expressed as an instantaneous rate in Hertz.
+    pub fn p90_hz(&self) -> f64 {
+        Self::hz(self.p90())
+    }
+
+    /// `p99()`, expressed as an instantaneous rate in Hertz.
+    pub fn p99_hz(&self) -> f64 {
+        Self::hz(self.p99())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_on_constant_intervals() {
+        let stats = RateStats::from_deltas(vec![0.01; 10]);
+        assert_eq!(stats.min(), Duration::from_secs_f64(0.01));
+        assert_eq!(stats.max(), Duration::from_secs_f64(0.01));
+        assert_eq!(stats.mean(), Duration::from_secs_f64(0.01));
+        assert!(stats.jitter() < 1e-9, "jitter should be ~0, was {}", stats.jitter());
+        assert_eq!(stats.p50(), Duration::from_secs_f64(0.01));
+        assert_eq!(stats.p99(), Duration::from_secs_f64(0.01));
+    }
+
+    #[test]
+    fn test_stats_percentiles_and_jitter_on_varied_intervals() {
+        // Nine fast intervals and one long stall.
+        let mut deltas = vec![0.01; 9];
+        deltas.push(0.1);
+        let stats = RateStats::from_deltas(deltas);
+
+        assert_eq!(stats.min(), Duration::from_secs_f64(0.01));
+        assert_eq!(stats.max(), Duration::from_secs_f64(0.1));
+        // p90 is the 9th of 10 sorted samples (nearest-rank), still a fast one.
+        assert_eq!(stats.p90(), Duration::from_secs_f64(0.01));
+        // p99 and the max both land on the stall.
+        assert_eq!(stats.p99(), Duration::from_secs_f64(0.1));
+        assert!(
+            stats.jitter() > 0.0,
+            "jitter should be nonzero with a stall present"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stats_rejects_out_of_range_percentile() {
+        let stats = RateStats::from_deltas(vec![0.01]);
+        stats.percentile(0.0);
+    }
+}